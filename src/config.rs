@@ -0,0 +1,149 @@
+//! Tunable scoring parameters for [`crate::Analysis::minified_probability`].
+
+use crate::Language;
+
+/// Scoring parameters for [`crate::Analysis::minified_probability`] and
+/// [`crate::Analysis::is_likely_minified`]
+///
+/// Build one with [`Config::builder`], or use [`Config::default`] /
+/// [`Config::for_language`] to start from the crate's built-in defaults.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub(crate) space_weight: f32,
+    pub(crate) name_weight: f32,
+    pub(crate) shape_weight: f32,
+    pub(crate) line_weight: f32,
+    pub(crate) max_space_ratio: f32,
+    pub(crate) ident_length_bounds: (usize, usize),
+    pub(crate) shape_bounds: (f32, f32),
+    pub(crate) longest_line_bounds: (usize, usize),
+    pub(crate) threshold: f32,
+    pub(crate) source_map_boost: f32,
+    pub(crate) banner_boost: f32,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            space_weight: 0.1,
+            name_weight: 0.4,
+            shape_weight: 0.2,
+            line_weight: 0.3,
+            max_space_ratio: 0.5,
+            ident_length_bounds: (1, 6),
+            shape_bounds: (0.0, 20.0),
+            longest_line_bounds: (0, 1000),
+            threshold: 0.5,
+            source_map_boost: 0.35,
+            banner_boost: 0.15,
+        }
+    }
+}
+
+impl Config {
+    /// Creates a [`ConfigBuilder`] seeded with the crate's defaults
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::default()
+    }
+
+    /// Returns scoring defaults tuned for the given [`Language`]
+    ///
+    /// CSS keeps readable property and selector names even when minified,
+    /// so the identifier-length weight is lower and the layout weights pick
+    /// up the difference.
+    pub fn for_language(language: Language) -> Config {
+        match language {
+            Language::JavaScript => Config::default(),
+            Language::Css => Config {
+                space_weight: 0.2,
+                name_weight: 0.15,
+                shape_weight: 0.25,
+                line_weight: 0.4,
+                ..Config::default()
+            },
+        }
+    }
+}
+
+/// Builder for [`Config`]
+#[derive(Debug, Clone, Default)]
+pub struct ConfigBuilder {
+    cfg: Config,
+}
+
+impl ConfigBuilder {
+    /// Sets the weight given to the whitespace-to-code ratio signal
+    pub fn space_weight(mut self, weight: f32) -> Self {
+        self.cfg.space_weight = weight;
+        self
+    }
+
+    /// Sets the weight given to the identifier-median-length signal
+    pub fn name_weight(mut self, weight: f32) -> Self {
+        self.cfg.name_weight = weight;
+        self
+    }
+
+    /// Sets the weight given to the file "shape" signal
+    pub fn shape_weight(mut self, weight: f32) -> Self {
+        self.cfg.shape_weight = weight;
+        self
+    }
+
+    /// Sets the weight given to the longest-line signal
+    pub fn line_weight(mut self, weight: f32) -> Self {
+        self.cfg.line_weight = weight;
+        self
+    }
+
+    /// Sets the whitespace-to-code ratio ceiling past which the signal
+    /// saturates
+    pub fn max_space_ratio(mut self, ratio: f32) -> Self {
+        self.cfg.max_space_ratio = ratio;
+        self
+    }
+
+    /// Sets the `(min, max)` clamp applied to the median identifier length
+    pub fn ident_length_bounds(mut self, min: usize, max: usize) -> Self {
+        self.cfg.ident_length_bounds = (min, max);
+        self
+    }
+
+    /// Sets the `(min, max)` clamp applied to the file shape
+    pub fn shape_bounds(mut self, min: f32, max: f32) -> Self {
+        self.cfg.shape_bounds = (min, max);
+        self
+    }
+
+    /// Sets the `(min, max)` clamp applied to the longest line length
+    pub fn longest_line_bounds(mut self, min: usize, max: usize) -> Self {
+        self.cfg.longest_line_bounds = (min, max);
+        self
+    }
+
+    /// Sets the probability threshold past which a file is considered
+    /// likely minified
+    pub fn threshold(mut self, threshold: f32) -> Self {
+        self.cfg.threshold = threshold;
+        self
+    }
+
+    /// Sets the flat probability boost applied when the file carries a
+    /// `sourceMappingURL` directive
+    pub fn source_map_boost(mut self, boost: f32) -> Self {
+        self.cfg.source_map_boost = boost;
+        self
+    }
+
+    /// Sets the flat probability boost applied when the file opens with a
+    /// preserved license/banner comment followed by dense code
+    pub fn banner_boost(mut self, boost: f32) -> Self {
+        self.cfg.banner_boost = boost;
+        self
+    }
+
+    /// Builds the final [`Config`]
+    pub fn build(self) -> Config {
+        self.cfg
+    }
+}
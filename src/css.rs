@@ -0,0 +1,37 @@
+//! CSS-specific tokenization used by [`crate::analyze_str_as`] when scanning
+//! stylesheets instead of JavaScript.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    /// Tokenizes CSS into comments, strings, at-rules, hex colors and
+    /// selector/property identifiers.  Just like `BASIC_TOKEN_RE` this is
+    /// "good enough" rather than a real CSS parser.
+    pub(crate) static ref CSS_TOKEN_RE: Regex = Regex::new(
+        r#"(?mx)
+        (?P<comment>
+            (?s:/\*.*?\*/)) |
+        (?P<whitespace>
+            \s+) |
+        (?P<string>
+            '([^'\\]*(?:\\.[^'\\]*)*)' |
+            "([^"\\]*(?:\\.[^"\\]*)*)") |
+        (?P<at_rule>
+            @[A-Za-z-]+) |
+        (?P<hex_color>
+            \#[0-9A-Fa-f]{3,8}\b) |
+        (?P<ident>
+            -?[A-Za-z_][A-Za-z0-9_-]*)
+    "#
+    )
+    .unwrap();
+
+    pub(crate) static ref CSS_IDENT_RE: Regex =
+        Regex::new(r#"-?[A-Za-z_][A-Za-z0-9_-]*"#).unwrap();
+
+    /// A rough match for a `property: value;` pair, used to tell CSS apart
+    /// from JavaScript when the caller doesn't know which one they have.
+    pub(crate) static ref CSS_RULE_RE: Regex =
+        Regex::new(r"[{;]\s*[-A-Za-z]+\s*:\s*[^;{}]+;").unwrap();
+}
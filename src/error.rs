@@ -0,0 +1,82 @@
+//! Error handling for the reader-based entry points.
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::str::Utf8Error;
+
+/// How much context (in bytes) to show around a decoding failure
+const CONTEXT_RADIUS: usize = 20;
+
+/// Positional context for a UTF-8 decoding failure
+#[derive(Debug)]
+pub struct Utf8Context {
+    position: usize,
+    context: String,
+}
+
+impl Utf8Context {
+    fn new(buf: &[u8], err: Utf8Error) -> Utf8Context {
+        let position = err.valid_up_to();
+        let start = position.saturating_sub(CONTEXT_RADIUS);
+        let end = (position + CONTEXT_RADIUS).min(buf.len());
+        Utf8Context {
+            position,
+            context: String::from_utf8_lossy(&buf[start..end]).into_owned(),
+        }
+    }
+
+    /// The byte offset at which decoding broke
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// A short, lossily-decoded slice of code surrounding the failure
+    pub fn context(&self) -> &str {
+        &self.context
+    }
+}
+
+/// Errors that can occur while analyzing a [`std::io::Read`]er
+#[derive(Debug)]
+pub enum AnalyzeError {
+    /// Reading from the underlying reader failed
+    Io(io::Error),
+    /// The input was not valid UTF-8
+    InvalidUtf8(Utf8Context),
+}
+
+impl AnalyzeError {
+    pub(crate) fn from_utf8(buf: &[u8], err: Utf8Error) -> AnalyzeError {
+        AnalyzeError::InvalidUtf8(Utf8Context::new(buf, err))
+    }
+}
+
+impl fmt::Display for AnalyzeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AnalyzeError::Io(err) => write!(f, "failed to read source: {}", err),
+            AnalyzeError::InvalidUtf8(ctx) => write!(
+                f,
+                "invalid UTF-8 at byte {}, near {:?}",
+                ctx.position(),
+                ctx.context()
+            ),
+        }
+    }
+}
+
+impl Error for AnalyzeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AnalyzeError::Io(err) => Some(err),
+            AnalyzeError::InvalidUtf8(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for AnalyzeError {
+    fn from(err: io::Error) -> AnalyzeError {
+        AnalyzeError::Io(err)
+    }
+}
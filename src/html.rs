@@ -0,0 +1,144 @@
+//! Support for analyzing inline `<script>` and `<style>` blocks embedded in
+//! an HTML document.
+
+use std::io::Read;
+use std::ops::Range;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::{analyze_str_as, AnalyzeError, Analysis, Language};
+
+lazy_static! {
+    static ref SCRIPT_OPEN_RE: Regex = Regex::new(r#"(?is)<script\b([^>]*)>"#).unwrap();
+    static ref STYLE_OPEN_RE: Regex = Regex::new(r#"(?is)<style\b([^>]*)>"#).unwrap();
+    static ref SRC_ATTR_RE: Regex = Regex::new(r#"(?is)\bsrc\s*="#).unwrap();
+    static ref TYPE_ATTR_RE: Regex =
+        Regex::new(r#"(?is)\btype\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#).unwrap();
+}
+
+/// The analysis of a single embedded `<script>` or `<style>` block
+pub struct EmbeddedAnalysis {
+    /// The language of the embedded block
+    pub language: Language,
+    /// The byte range of the block's body (excluding the tags) in the
+    /// original document
+    pub range: Range<usize>,
+    /// The analysis of the block's body
+    pub analysis: Analysis,
+}
+
+/// Returns `true` if the `type` attribute (if any) in `attrs` indicates
+/// that a `<script>` tag contains plain JavaScript.
+fn is_js_script(attrs: &str) -> bool {
+    match TYPE_ATTR_RE.captures(attrs) {
+        None => true,
+        Some(caps) => {
+            let value = caps
+                .get(1)
+                .or_else(|| caps.get(2))
+                .or_else(|| caps.get(3))
+                .map(|m| m.as_str())
+                .unwrap_or("");
+            matches!(
+                value.trim().to_ascii_lowercase().as_str(),
+                "" | "text/javascript" | "application/javascript" | "module"
+            )
+        }
+    }
+}
+
+/// Finds the first case-insensitive occurrence of `needle` in `haystack`.
+fn find_ci(haystack: &str, needle: &str) -> Option<usize> {
+    haystack.to_ascii_lowercase().find(needle)
+}
+
+fn scan_tag(
+    code: &str,
+    open_re: &Regex,
+    closing_tag: &str,
+    language: Language,
+    skip_non_js: bool,
+    out: &mut Vec<EmbeddedAnalysis>,
+) {
+    let mut pos = 0;
+    while pos < code.len() {
+        let caps = match open_re.captures(&code[pos..]) {
+            Some(caps) => caps,
+            None => break,
+        };
+        let whole = caps.get(0).unwrap();
+        let attrs = caps.get(1).map(|m| m.as_str()).unwrap_or("");
+        let body_start = pos + whole.end();
+
+        if skip_non_js && (SRC_ATTR_RE.is_match(attrs) || !is_js_script(attrs)) {
+            pos = body_start;
+            continue;
+        }
+
+        let remainder = &code[body_start..];
+        let body_end = match find_ci(remainder, closing_tag) {
+            Some(offset) => body_start + offset,
+            None => code.len(),
+        };
+
+        out.push(EmbeddedAnalysis {
+            language,
+            range: body_start..body_end,
+            analysis: analyze_str_as(&code[body_start..body_end], language),
+        });
+
+        pos = body_end;
+    }
+}
+
+/// Analyze the inline `<script>` and `<style>` blocks of an HTML document
+///
+/// `<script>` tags that carry a `src` attribute (external scripts) or a
+/// `type` attribute that isn't plain JavaScript (e.g. `text/template`) are
+/// skipped since there is no embedded source to analyze.
+///
+/// Example:
+///
+/// ```rust
+/// # use might_be_minified::{analyze_html, Config};
+/// for embedded in analyze_html("<script>var x=1</script>") {
+///     let cfg = Config::for_language(embedded.language);
+///     println!("{:?}: {}", embedded.language, embedded.analysis.is_likely_minified(&cfg));
+/// }
+/// ```
+pub fn analyze_html(code: &str) -> Vec<EmbeddedAnalysis> {
+    let mut out = Vec::new();
+    scan_tag(
+        code,
+        &SCRIPT_OPEN_RE,
+        "</script",
+        Language::JavaScript,
+        true,
+        &mut out,
+    );
+    scan_tag(
+        code,
+        &STYLE_OPEN_RE,
+        "</style",
+        Language::Css,
+        false,
+        &mut out,
+    );
+    out.sort_by_key(|e| e.range.start);
+    out
+}
+
+/// Analyze the inline `<script>` and `<style>` blocks of an HTML document
+/// behind a reader
+///
+/// Like [`crate::analyze`] this can fail on I/O errors or invalid UTF-8;
+/// see [`crate::AnalyzeError`].
+pub fn analyze_html_reader<R: Read>(mut rdr: R) -> Result<Vec<EmbeddedAnalysis>, AnalyzeError> {
+    let mut buf = Vec::new();
+    rdr.read_to_end(&mut buf)?;
+    match std::str::from_utf8(&buf) {
+        Ok(code) => Ok(analyze_html(code)),
+        Err(err) => Err(AnalyzeError::from_utf8(&buf, err)),
+    }
+}
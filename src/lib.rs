@@ -7,12 +7,33 @@ use std::io::Read;
 use lazy_static::lazy_static;
 use regex::Regex;
 
+mod config;
+mod css;
+mod error;
+mod html;
+mod streaming;
+
+pub use config::{Config, ConfigBuilder};
+pub use error::{AnalyzeError, Utf8Context};
+pub use html::{analyze_html, analyze_html_reader, EmbeddedAnalysis};
+pub use streaming::{analyze_streaming, analyze_streaming_as};
+
+/// The source language being analyzed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    /// JavaScript source
+    JavaScript,
+    /// CSS source
+    Css,
+}
+
 lazy_static! {
     static ref BASIC_TOKEN_RE: Regex = Regex::new(
         &r#"(?mx)
         (?P<comment>
+            (?s:
             //.*?$ |
-            /\*.*?\*/) |
+            /\*.*?\*/)) |
         (?P<whitespace>
             \s+) |
         (?P<string>
@@ -77,6 +98,32 @@ lazy_static! {
     .unwrap();
 }
 
+/// Returns `true` if the text immediately following a comment looks like
+/// densely packed code rather than hand-formatted source.
+///
+/// This only looks at a bounded prefix of the remaining source so it stays
+/// cheap even when called on the very first comment of a huge bundle.
+pub(crate) fn looks_like_dense_code(following: &str) -> bool {
+    let end = following
+        .char_indices()
+        .nth(200)
+        .map(|(i, _)| i)
+        .unwrap_or_else(|| following.len());
+    let sample = following[..end].trim_start();
+    if sample.is_empty() {
+        return false;
+    }
+    let mut space = 0usize;
+    let mut total = 0usize;
+    for c in sample.chars() {
+        total += 1;
+        if c.is_whitespace() {
+            space += 1;
+        }
+    }
+    (space as f32 / total as f32) < 0.05
+}
+
 fn partial_clamp<T: PartialOrd>(l: T, u: T, v: T) -> T {
     if v < l {
         l
@@ -89,10 +136,13 @@ fn partial_clamp<T: PartialOrd>(l: T, u: T, v: T) -> T {
 
 /// Provides an analysis of a source file
 pub struct Analysis {
+    language: Language,
     line_lengths: Vec<usize>,
     ident_lengths: Vec<usize>,
     space_count: usize,
     non_space_count: usize,
+    has_source_map_directive: bool,
+    has_preserved_banner: bool,
 }
 
 /// Analyze JavaScript contained in a script
@@ -100,12 +150,32 @@ pub struct Analysis {
 /// Example:
 ///
 /// ```rust
-/// # use might_be_minified::analyze_str;
-/// if analyze_str("...").is_likely_minified() {
+/// # use might_be_minified::{analyze_str, Config};
+/// if analyze_str("...").is_likely_minified(&Config::default()) {
 ///     println!("This is probably a minified file");
 /// }
 /// ```
 pub fn analyze_str(code: &str) -> Analysis {
+    analyze_str_as(code, Language::JavaScript)
+}
+
+/// Analyze source of a given [`Language`]
+///
+/// This generalizes [`analyze_str`] to stylesheets: CSS keeps readable
+/// property names even when minified, so the identifier histogram means
+/// something different there than it does for JavaScript.
+///
+/// Example:
+///
+/// ```rust
+/// # use might_be_minified::{analyze_str_as, Config, Language};
+/// if analyze_str_as("a{color:red}", Language::Css)
+///     .is_likely_minified(&Config::for_language(Language::Css))
+/// {
+///     println!("This is probably a minified stylesheet");
+/// }
+/// ```
+pub fn analyze_str_as(code: &str, language: Language) -> Analysis {
     let mut line_lengths = vec![];
     let mut ident_lengths = vec![];
     let mut space = 0;
@@ -142,31 +212,165 @@ pub fn analyze_str(code: &str) -> Analysis {
     // shitty tokenization.  This is known to be broken but it's "good enough"
     // to do a basic detection on if this is javascript or not.  In particular
     // we count keywords and a name length histogram.
-    for m in BASIC_TOKEN_RE.find_iter(code) {
-        if IDENT_RE.is_match(m.as_str()) {
+    let token_re: &Regex = match language {
+        Language::JavaScript => &BASIC_TOKEN_RE,
+        Language::Css => &css::CSS_TOKEN_RE,
+    };
+    let ident_re: &Regex = match language {
+        Language::JavaScript => &IDENT_RE,
+        Language::Css => &css::CSS_IDENT_RE,
+    };
+    for m in token_re.find_iter(code) {
+        if ident_re.is_match(m.as_str()) {
             ident_lengths.push(m.end() - m.start());
         }
     }
 
+    // dedicated pass over the comments to pick up explicit build-tool
+    // signals that the layout heuristics alone can't see.
+    let mut has_source_map_directive = false;
+    let mut has_preserved_banner = false;
+    let mut seen_first_comment = false;
+    for caps in token_re.captures_iter(code) {
+        let m = match caps.name("comment") {
+            Some(m) => m,
+            None => continue,
+        };
+        let trimmed = m.as_str().trim_start();
+
+        if trimmed.starts_with("//# sourceMappingURL=")
+            || (trimmed.starts_with("/*#") && trimmed.contains("sourceMappingURL="))
+        {
+            has_source_map_directive = true;
+        }
+
+        if !seen_first_comment {
+            seen_first_comment = true;
+            let is_banner = trimmed.starts_with("/*!")
+                || (trimmed.starts_with("/*")
+                    && (trimmed.contains("@license") || trimmed.contains("@preserve")));
+            if is_banner && looks_like_dense_code(&code[m.end()..]) {
+                has_preserved_banner = true;
+            }
+        }
+    }
+
     line_lengths.sort();
     ident_lengths.sort();
 
     Analysis {
-        line_lengths: line_lengths,
-        ident_lengths: ident_lengths,
+        language,
+        line_lengths,
+        ident_lengths,
         space_count: space,
         non_space_count: not_space,
+        has_source_map_directive,
+        has_preserved_banner,
+    }
+}
+
+/// Guesses the [`Language`] of a source snippet
+///
+/// This is a cheap heuristic, not a parser: it compares how often the text
+/// looks like CSS `property: value;` rules against how often it looks like
+/// JavaScript keywords and operators, and picks whichever is more common.
+///
+/// Example:
+///
+/// ```rust
+/// # use might_be_minified::{detect_language, Language};
+/// assert_eq!(detect_language("a{color:red;}"), Language::Css);
+/// ```
+pub fn detect_language(code: &str) -> Language {
+    let css_hits = css::CSS_RULE_RE.find_iter(code).count();
+    let js_hits = BASIC_TOKEN_RE
+        .captures_iter(code)
+        .filter(|caps| caps.name("keyword").is_some() || caps.name("regex_op").is_some())
+        .count();
+
+    if css_hits > js_hits {
+        Language::Css
+    } else {
+        Language::JavaScript
     }
 }
 
 /// Analyze JavaScript behind a reader
-pub fn analyze<R: Read>(mut rdr: R) -> Analysis {
-    let mut rv = String::new();
-    rdr.read_to_string(&mut rv).unwrap();
-    analyze_str(&rv)
+///
+/// Unlike [`analyze_str`] this can fail: the reader might return an I/O
+/// error, or the bytes it yields might not be valid UTF-8.  Use
+/// [`analyze_lossy`] if you'd rather substitute replacement characters than
+/// fail on the latter.
+pub fn analyze<R: Read>(rdr: R) -> Result<Analysis, AnalyzeError> {
+    analyze_as(rdr, Language::JavaScript)
+}
+
+/// Analyze source of a given [`Language`] behind a reader
+///
+/// See [`analyze`] for the error behavior.
+pub fn analyze_as<R: Read>(mut rdr: R, language: Language) -> Result<Analysis, AnalyzeError> {
+    let mut buf = Vec::new();
+    rdr.read_to_end(&mut buf)?;
+    match std::str::from_utf8(&buf) {
+        Ok(code) => Ok(analyze_str_as(code, language)),
+        Err(err) => Err(AnalyzeError::from_utf8(&buf, err)),
+    }
+}
+
+/// Analyze JavaScript behind a reader, substituting replacement characters
+/// for any invalid UTF-8 instead of failing
+///
+/// This is useful for binary-ish or latin1 minified payloads that should
+/// still get scored rather than rejected outright.
+pub fn analyze_lossy<R: Read>(rdr: R) -> Result<Analysis, AnalyzeError> {
+    analyze_lossy_as(rdr, Language::JavaScript)
+}
+
+/// Analyze source of a given [`Language`] behind a reader, substituting
+/// replacement characters for any invalid UTF-8 instead of failing
+pub fn analyze_lossy_as<R: Read>(
+    mut rdr: R,
+    language: Language,
+) -> Result<Analysis, AnalyzeError> {
+    let mut buf = Vec::new();
+    rdr.read_to_end(&mut buf)?;
+    let code = String::from_utf8_lossy(&buf);
+    Ok(analyze_str_as(&code, language))
 }
 
 impl Analysis {
+    /// Builds an `Analysis` from already-computed metrics
+    ///
+    /// Used by [`analyze_streaming_as`] to assemble the same public shape
+    /// that [`analyze_str_as`] produces, without exposing the private
+    /// fields outside the crate.
+    pub(crate) fn from_parts(
+        language: Language,
+        mut line_lengths: Vec<usize>,
+        mut ident_lengths: Vec<usize>,
+        space_count: usize,
+        non_space_count: usize,
+        has_source_map_directive: bool,
+        has_preserved_banner: bool,
+    ) -> Analysis {
+        line_lengths.sort();
+        ident_lengths.sort();
+        Analysis {
+            language,
+            line_lengths,
+            ident_lengths,
+            space_count,
+            non_space_count,
+            has_source_map_directive,
+            has_preserved_banner,
+        }
+    }
+
+    /// The language this analysis was performed as
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
     /// Returns the whitespace to code ratio
     ///
     /// This is a useful metric to decide on if a file is likely minified code
@@ -177,8 +381,10 @@ impl Analysis {
 
     /// The median identifier length
     ///
-    /// This returns the median length for an identifier (name) in the JS
-    /// source code.
+    /// This returns the median length for an identifier (name) in the
+    /// source code.  For CSS this is a weaker signal than for JavaScript
+    /// since stylesheets tend to keep readable property and selector names
+    /// even when minified; see [`Analysis::minified_probability`].
     pub fn median_ident_length(&self) -> usize {
         if self.line_lengths.is_empty() {
             0
@@ -218,21 +424,76 @@ impl Analysis {
         height as f32 / width as f32
     }
 
+    /// Indicates that the file carries a `sourceMappingURL` directive
+    ///
+    /// This is essentially pathognomonic of build-tool output since hand
+    /// written source rarely carries it.
+    pub fn has_source_map_directive(&self) -> bool {
+        self.has_source_map_directive
+    }
+
+    /// Indicates that the file opens with a preserved license/banner comment
+    ///
+    /// This fires when the first comment in the file is a `/*!` or
+    /// `@license`/`@preserve` block comment that is immediately followed by
+    /// very dense code, which is a strong sign of a minified bundle that
+    /// kept its banner intact.
+    pub fn has_preserved_banner(&self) -> bool {
+        self.has_preserved_banner
+    }
+
     /// The proability of the file being minified
     ///
     /// Effectively 1.0 (which is unlikely to be reached) means the file is
-    /// definitely minified.  Anything above 0.5 is considered likely to be
-    /// minified.
-    pub fn minified_probability(&self) -> f32 {
-        let p_space = (0.5 - partial_clamp(0.0, 0.5, self.space_to_code_ratio())) * 2.0;
-        let p_name = (5 - (partial_clamp(1, 6, self.median_ident_length()) - 1)) as f32 / 5.0;
-        let p_shape = (20.0 - partial_clamp(0.0, 20.0, self.shape())) / 20.0;
-        let p_line = partial_clamp(0, 1000, self.longest_line()) as f32 / 1000.0;
-        (p_space * 0.1 + p_name * 0.4 + p_shape * 0.2 + p_line * 0.3)
+    /// definitely minified.  The signal weights and clamp bounds are read
+    /// from `cfg`; use [`Config::for_language`] to start from the tuning
+    /// appropriate for this analysis's [`Language`].
+    pub fn minified_probability(&self, cfg: &Config) -> f32 {
+        let p_space = (cfg.max_space_ratio
+            - partial_clamp(0.0, cfg.max_space_ratio, self.space_to_code_ratio()))
+            / cfg.max_space_ratio;
+        let (lo, hi) = cfg.ident_length_bounds;
+        let p_name =
+            (hi - partial_clamp(lo, hi, self.median_ident_length())) as f32 / (hi - lo) as f32;
+        let (lo, hi) = cfg.shape_bounds;
+        let p_shape = (hi - partial_clamp(lo, hi, self.shape())) / (hi - lo);
+        let (lo, hi) = cfg.longest_line_bounds;
+        let p_line =
+            (partial_clamp(lo, hi, self.longest_line()) - lo) as f32 / (hi - lo) as f32;
+        let mut p = p_space * cfg.space_weight
+            + p_name * cfg.name_weight
+            + p_shape * cfg.shape_weight
+            + p_line * cfg.line_weight;
+        if self.has_source_map_directive {
+            p = (p + cfg.source_map_boost).min(1.0);
+        }
+        if self.has_preserved_banner {
+            p = (p + cfg.banner_boost).min(1.0);
+        }
+        p
     }
 
     /// Indicates that the file is likely minified
-    pub fn is_likely_minified(&self) -> bool {
-        self.minified_probability() > 0.5
+    pub fn is_likely_minified(&self, cfg: &Config) -> bool {
+        self.minified_probability(cfg) > cfg.threshold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multiline_banner_comment_is_detected() {
+        let code = "/*!\n * @license MIT\n */\na=1;b=2;c=3;d=4;e=5;f=6;g=7;h=8;i=9;j=10;";
+        let analysis = analyze_str(code);
+        assert!(analysis.has_preserved_banner());
+    }
+
+    #[test]
+    fn multiline_source_map_comment_is_detected() {
+        let code = "function f(){return 1}\n/*# sourceMappingURL=f.js.map\n*/\n";
+        let analysis = analyze_str(code);
+        assert!(analysis.has_source_map_directive());
     }
 }
@@ -0,0 +1,531 @@
+//! Allocation-light, streaming variant of [`crate::analyze`] for large
+//! bundles.
+//!
+//! [`analyze_str_as`](crate::analyze_str_as) buffers the whole file and
+//! runs a multiline regex over it, which is fine for typical assets but
+//! costs `O(n)` memory and a full regex pass on multi-megabyte bundles --
+//! exactly the files this crate spends most of its time on. The functions
+//! here instead read the source in bounded windows and compute the same
+//! metrics with a hand-rolled byte scanner: `memchr` finds line breaks for
+//! the layout histogram, and a small state machine tracks whether the
+//! scanner is inside a string or a comment so that a token straddling a
+//! window boundary is still handled correctly.
+//!
+//! This scanner is ASCII-oriented: non-ASCII bytes are treated as opaque
+//! "maybe identifier" bytes rather than being decoded and classified by
+//! Unicode category like [`analyze_str_as`] does. For the overwhelmingly
+//! ASCII-dominant minified bundles this crate targets the output matches
+//! [`analyze_str_as`] byte for byte. Known divergences are narrow: a
+//! document with a lot of non-ASCII text, an unterminated string/comment
+//! that runs off the end of the file, a `sourceMappingURL=` or
+//! `@license`/`@preserve` marker that does not fit in the first 512 bytes
+//! of its comment, or (vanishingly rarely) a short token whose match
+//! depends on the bytes after it -- a CSS hex color, at-rule or leading
+//! `-`, or a JavaScript `\/` -- split exactly across a window boundary.
+
+use std::io::Read;
+
+use memchr::memchr;
+
+use crate::{AnalyzeError, Analysis, Language};
+
+/// Size of the read buffer used to pull bytes from the reader
+const WINDOW_SIZE: usize = 64 * 1024;
+
+/// How much of a comment's text we keep around to check for a
+/// `sourceMappingURL` directive or a license banner. The comment's full
+/// *length* is still tracked exactly; this only bounds the memory used to
+/// inspect its contents.
+const COMMENT_SAMPLE_CAP: usize = 512;
+
+/// How many characters after a candidate banner comment we sample to
+/// decide whether it's followed by dense code
+const BANNER_SAMPLE_CAP: usize = 200;
+
+#[derive(Clone, Copy, PartialEq)]
+enum TokenState {
+    Normal,
+    LineComment,
+    BlockComment,
+    Str(u8),
+}
+
+struct Scanner {
+    language: Language,
+
+    // layout metrics
+    line_lengths: Vec<usize>,
+    line_width: usize,
+    space: usize,
+    non_space: usize,
+
+    // tokenizer state, carried across windows
+    state: TokenState,
+    pending_slash: bool,
+    block_comment_saw_star: bool,
+    string_escape_next: bool,
+
+    word_len: usize,
+    ident_lengths: Vec<usize>,
+
+    comment_len: usize,
+    comment_has_ident: bool,
+    comment_sample: String,
+
+    string_len: usize,
+    string_has_ident: bool,
+
+    seen_first_comment: bool,
+    banner_sample: Option<String>,
+
+    has_source_map_directive: bool,
+    has_preserved_banner: bool,
+}
+
+impl Scanner {
+    fn new(language: Language) -> Scanner {
+        Scanner {
+            language,
+            line_lengths: Vec::new(),
+            line_width: 0,
+            space: 0,
+            non_space: 1,
+            state: TokenState::Normal,
+            pending_slash: false,
+            block_comment_saw_star: false,
+            string_escape_next: false,
+            word_len: 0,
+            ident_lengths: Vec::new(),
+            comment_len: 0,
+            comment_has_ident: false,
+            comment_sample: String::new(),
+            string_len: 0,
+            string_has_ident: false,
+            seen_first_comment: false,
+            banner_sample: None,
+            has_source_map_directive: false,
+            has_preserved_banner: false,
+        }
+    }
+
+    fn feed(&mut self, chunk: &[u8]) {
+        self.feed_layout(chunk);
+        self.feed_tokens(chunk);
+    }
+
+    /// Layout pass: whitespace/non-whitespace counts and the line-length
+    /// histogram, using `memchr` to jump straight to each line break.
+    fn feed_layout(&mut self, chunk: &[u8]) {
+        let mut start = 0;
+        while start < chunk.len() {
+            match memchr(b'\n', &chunk[start..]) {
+                Some(rel) => {
+                    self.consume_line_bytes(&chunk[start..start + rel]);
+                    // the newline itself counts as whitespace, just like
+                    // `c.is_whitespace()` does for `\n` in `analyze_str_as`.
+                    self.space += 1;
+                    if self.line_width > 0 {
+                        self.line_lengths.push(self.line_width);
+                    }
+                    self.line_width = 0;
+                    start += rel + 1;
+                }
+                None => {
+                    self.consume_line_bytes(&chunk[start..]);
+                    break;
+                }
+            }
+        }
+    }
+
+    fn consume_line_bytes(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            if b == b'\t' {
+                self.space += 4;
+            } else if is_ascii_space(b) {
+                self.space += 1;
+            } else {
+                self.non_space += 1;
+            }
+            if b != b'\r' {
+                self.line_width += if b == b'\t' { 4 } else { 1 };
+            }
+        }
+    }
+
+    /// Tokenizer pass: identifier/keyword lengths and comment/string
+    /// detection, carrying `Str`/`*Comment` state across window calls.
+    fn feed_tokens(&mut self, chunk: &[u8]) {
+        let mut i = 0;
+        while i < chunk.len() {
+            let b = chunk[i];
+
+            self.sample_banner(&chunk[i..i + 1]);
+
+            match self.state {
+                TokenState::Str(quote) => {
+                    self.string_len += 1;
+                    if is_word_start_byte(b, self.language) {
+                        self.string_has_ident = true;
+                    }
+                    if self.string_escape_next {
+                        self.string_escape_next = false;
+                    } else if b == b'\\' {
+                        self.string_escape_next = true;
+                    } else if b == quote {
+                        self.close_string();
+                    }
+                    i += 1;
+                }
+                TokenState::LineComment => {
+                    if b == b'\n' {
+                        self.close_comment();
+                    } else {
+                        self.push_comment_byte(b);
+                    }
+                    i += 1;
+                }
+                TokenState::BlockComment => {
+                    self.push_comment_byte(b);
+                    if self.block_comment_saw_star && b == b'/' {
+                        self.close_comment();
+                        self.block_comment_saw_star = false;
+                    } else {
+                        self.block_comment_saw_star = b == b'*';
+                    }
+                    i += 1;
+                }
+                TokenState::Normal => {
+                    if self.word_len > 0 && is_word_byte(b, self.language) {
+                        self.word_len += 1;
+                        i += 1;
+                        continue;
+                    }
+                    self.flush_word();
+
+                    if self.pending_slash {
+                        self.pending_slash = false;
+                        if b == b'*' {
+                            self.open_comment(true);
+                            i += 1;
+                            continue;
+                        } else if b == b'/' && self.language == Language::JavaScript {
+                            self.open_comment(false);
+                            i += 1;
+                            continue;
+                        }
+                        // the earlier lone '/' matched nothing; fall
+                        // through and re-examine `b` fresh below.
+                    }
+
+                    if b == b'\\'
+                        && self.language == Language::JavaScript
+                        && chunk.get(i + 1) == Some(&b'/')
+                    {
+                        // BASIC_TOKEN_RE's `regex_op` group takes `\/` as one
+                        // token, so this `/` never opens a comment.
+                        self.sample_banner(&chunk[i + 1..i + 2]);
+                        i += 2;
+                        continue;
+                    }
+
+                    if b == b'/' {
+                        // whether this opens a comment depends on the next
+                        // byte, which the `pending_slash` branch above looks
+                        // at on the next step (in this window or the next).
+                        self.pending_slash = true;
+                        i += 1;
+                        continue;
+                    }
+
+                    if b == b'\'' || b == b'"' || (b == b'`' && self.language == Language::JavaScript) {
+                        self.state = TokenState::Str(b);
+                        self.string_len = 1;
+                        self.string_has_ident = false;
+                        self.string_escape_next = false;
+                        i += 1;
+                        continue;
+                    }
+
+                    if b == b'#' && self.language == Language::Css {
+                        // mirrors CSS_TOKEN_RE's `hex_color` group: `#`
+                        // followed by 3-8 hex digits and a word boundary,
+                        // matched as one token of that whole length (only
+                        // within this window -- see the module docs).
+                        let start = i + 1;
+                        let mut end = start;
+                        while end < chunk.len() && end < start + 8 && chunk[end].is_ascii_hexdigit()
+                        {
+                            end += 1;
+                        }
+                        let digit_count = end - start;
+                        let at_boundary = chunk
+                            .get(end)
+                            .map_or(true, |&c| !(c.is_ascii_alphanumeric() || c == b'_' || c >= 0x80));
+                        if digit_count >= 3 && at_boundary {
+                            if chunk[start..end].iter().any(u8::is_ascii_alphabetic) {
+                                self.ident_lengths.push(1 + digit_count);
+                            }
+                            self.sample_banner(&chunk[start..end]);
+                            i = end;
+                            continue;
+                        }
+                        // no hex color here: `#` matches no token group and
+                        // is skipped; the remaining bytes are reprocessed as
+                        // ordinary words below.
+                        i += 1;
+                        continue;
+                    }
+
+                    if b == b'@' && self.language == Language::Css {
+                        // mirrors CSS_TOKEN_RE's `at_rule` group: `@` plus
+                        // the letters and dashes after it, as one token.
+                        let start = i + 1;
+                        let mut end = start;
+                        while end < chunk.len() && (chunk[end].is_ascii_alphabetic() || chunk[end] == b'-') {
+                            end += 1;
+                        }
+                        if end > start {
+                            if chunk[start..end].iter().any(u8::is_ascii_alphabetic) {
+                                self.ident_lengths.push(1 + end - start);
+                            }
+                            self.sample_banner(&chunk[start..end]);
+                            i = end;
+                            continue;
+                        }
+                    }
+
+                    // a CSS ident starts with `-` only when a letter or `_`
+                    // follows, as in CSS_TOKEN_RE's `ident` group.
+                    let css_dash_start = b == b'-'
+                        && self.language == Language::Css
+                        && chunk.get(i + 1).map_or(true, |&c| c.is_ascii_alphabetic() || c == b'_');
+                    if is_word_start_byte(b, self.language) || css_dash_start {
+                        self.word_len = 1;
+                        i += 1;
+                        continue;
+                    }
+
+                    // matches none of the token groups; skip, just like
+                    // `find_iter` moving on to the next candidate start.
+                    i += 1;
+                }
+            }
+        }
+    }
+
+    /// Adds `bytes` to the banner sample, if one is being collected, up to
+    /// `BANNER_SAMPLE_CAP`.
+    fn sample_banner(&mut self, bytes: &[u8]) {
+        if let Some(sample) = self.banner_sample.as_mut() {
+            for &b in bytes {
+                if sample.len() < BANNER_SAMPLE_CAP {
+                    sample.push(b as char);
+                }
+            }
+        }
+    }
+
+    fn flush_word(&mut self) {
+        if self.word_len > 0 {
+            self.ident_lengths.push(self.word_len);
+            self.word_len = 0;
+        }
+    }
+
+    fn open_comment(&mut self, is_block: bool) {
+        self.state = if is_block {
+            TokenState::BlockComment
+        } else {
+            TokenState::LineComment
+        };
+        self.block_comment_saw_star = false;
+        self.comment_len = 0;
+        self.comment_has_ident = false;
+        self.comment_sample.clear();
+        self.push_comment_byte(b'/');
+        self.push_comment_byte(if is_block { b'*' } else { b'/' });
+    }
+
+    fn push_comment_byte(&mut self, b: u8) {
+        self.comment_len += 1;
+        if is_word_start_byte(b, self.language) {
+            self.comment_has_ident = true;
+        }
+        if self.comment_sample.len() < COMMENT_SAMPLE_CAP {
+            self.comment_sample.push(b as char);
+        }
+    }
+
+    fn close_comment(&mut self) {
+        let trimmed_starts_with = |s: &str| self.comment_sample.trim_start().starts_with(s);
+        if trimmed_starts_with("//# sourceMappingURL=")
+            || (trimmed_starts_with("/*#") && self.comment_sample.contains("sourceMappingURL="))
+        {
+            self.has_source_map_directive = true;
+        }
+
+        if !self.seen_first_comment {
+            self.seen_first_comment = true;
+            let trimmed = self.comment_sample.trim_start();
+            let is_banner = trimmed.starts_with("/*!")
+                || (trimmed.starts_with("/*")
+                    && (trimmed.contains("@license") || trimmed.contains("@preserve")));
+            if is_banner {
+                self.banner_sample = Some(String::new());
+            }
+        }
+
+        if self.comment_has_ident {
+            self.ident_lengths.push(self.comment_len);
+        }
+        self.comment_len = 0;
+        self.comment_has_ident = false;
+        self.comment_sample.clear();
+        self.state = TokenState::Normal;
+    }
+
+    fn close_string(&mut self) {
+        if self.string_has_ident {
+            self.ident_lengths.push(self.string_len);
+        }
+        self.string_len = 0;
+        self.string_has_ident = false;
+        self.state = TokenState::Normal;
+    }
+
+    fn finish(mut self) -> Analysis {
+        self.flush_word();
+        // an unterminated comment or string running off the end of the
+        // file is a divergence from `analyze_str_as` (see module docs);
+        // we still finalize it rather than dropping its counts.
+        match self.state {
+            TokenState::LineComment | TokenState::BlockComment => self.close_comment(),
+            TokenState::Str(_) => self.close_string(),
+            TokenState::Normal => {}
+        }
+        if self.line_width > 0 {
+            self.line_lengths.push(self.line_width);
+        }
+        if let Some(sample) = &self.banner_sample {
+            if crate::looks_like_dense_code(sample) {
+                self.has_preserved_banner = true;
+            }
+        }
+
+        Analysis::from_parts(
+            self.language,
+            self.line_lengths,
+            self.ident_lengths,
+            self.space,
+            self.non_space,
+            self.has_source_map_directive,
+            self.has_preserved_banner,
+        )
+    }
+}
+
+fn is_ascii_space(b: u8) -> bool {
+    matches!(b, b' ' | b'\t' | b'\r' | b'\n' | 0x0b | 0x0c)
+}
+
+/// A byte that can start an identifier/keyword word
+///
+/// A CSS `-` is not one on its own; `feed_tokens` starts a word with it
+/// only when a letter or `_` follows.
+fn is_word_start_byte(b: u8, language: Language) -> bool {
+    match language {
+        Language::JavaScript => b.is_ascii_alphabetic() || b == b'_' || b == b'$' || b >= 0x80,
+        Language::Css => b.is_ascii_alphabetic() || b == b'_' || b >= 0x80,
+    }
+}
+
+/// A byte that can continue an identifier/keyword word once started
+fn is_word_byte(b: u8, language: Language) -> bool {
+    match language {
+        Language::JavaScript => b.is_ascii_alphanumeric() || b == b'_' || b == b'$' || b >= 0x80,
+        Language::Css => b.is_ascii_alphanumeric() || b == b'_' || b == b'-' || b >= 0x80,
+    }
+}
+
+/// Analyze JavaScript behind a reader without buffering the whole input
+///
+/// See the [module docs](self) for the tradeoffs versus [`crate::analyze`].
+pub fn analyze_streaming<R: Read>(rdr: R) -> Result<Analysis, AnalyzeError> {
+    analyze_streaming_as(rdr, Language::JavaScript)
+}
+
+/// Analyze source of a given [`Language`] behind a reader without
+/// buffering the whole input
+///
+/// See the [module docs](self) for the tradeoffs versus
+/// [`crate::analyze_str_as`].
+pub fn analyze_streaming_as<R: Read>(
+    mut rdr: R,
+    language: Language,
+) -> Result<Analysis, AnalyzeError> {
+    let mut scanner = Scanner::new(language);
+    let mut buf = [0u8; WINDOW_SIZE];
+    loop {
+        let n = rdr.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        scanner.feed(&buf[..n]);
+    }
+    Ok(scanner.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{analyze_str_as, Config};
+
+    fn assert_parity(code: &str, language: Language) {
+        let buffered = analyze_str_as(code, language);
+        let streamed = analyze_streaming_as(Cursor::new(code.as_bytes()), language).unwrap();
+        let cfg = Config::for_language(language);
+        assert_eq!(buffered.space_to_code_ratio(), streamed.space_to_code_ratio());
+        assert_eq!(buffered.median_ident_length(), streamed.median_ident_length());
+        assert_eq!(buffered.longest_line(), streamed.longest_line());
+        assert_eq!(buffered.shape(), streamed.shape());
+        assert_eq!(
+            buffered.has_source_map_directive(),
+            streamed.has_source_map_directive()
+        );
+        assert_eq!(
+            buffered.has_preserved_banner(),
+            streamed.has_preserved_banner()
+        );
+        assert_eq!(
+            buffered.minified_probability(&cfg),
+            streamed.minified_probability(&cfg)
+        );
+    }
+
+    #[test]
+    fn multiline_banner_comment_matches_buffered_analysis() {
+        assert_parity(
+            "/*!\n * @license MIT\n */\na=1;b=2;c=3;d=4;e=5;f=6;g=7;h=8;i=9;j=10;",
+            Language::JavaScript,
+        );
+    }
+
+    #[test]
+    fn multiline_source_map_comment_matches_buffered_analysis() {
+        assert_parity(
+            "function f(){return 1}\n/*# sourceMappingURL=f.js.map\n*/\n",
+            Language::JavaScript,
+        );
+    }
+
+    #[test]
+    fn multiline_css_comment_matches_buffered_analysis() {
+        assert_parity(
+            "/*!\n * @license MIT\n */\na{color:#fff}b{color:#000}\n",
+            Language::Css,
+        );
+    }
+}